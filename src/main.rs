@@ -1,10 +1,10 @@
 use itertools::Itertools;
+use rayon::prelude::*;
 
 use rand::{seq::SliceRandom, thread_rng, Rng};
 use std::{
     collections::HashMap,
     io::{self, stdout},
-    ops::Index,
 };
 
 use crossterm::{
@@ -25,7 +25,7 @@ struct Game {
     pyramid: Pyramid,
 }
 
-#[derive(Clone)]
+#[derive(Debug, PartialEq, Clone)]
 struct Track {
     spaces: [Vec<CamelColor>; 16],
 }
@@ -36,37 +36,355 @@ impl Track {
         Track { spaces }
     }
 
+    /// Load a board position read off a real Camel Up board: one entry per
+    /// camel giving its square index, listed bottom-to-top within a shared
+    /// square. Every racing color must appear exactly once and every square
+    /// index must be on the track; a crazy camel left unspecified is placed
+    /// on the far end, same as `Game::new`.
+    fn set_state(&mut self, camels: &[(CamelColor, usize)]) -> Result<(), TrackStateError> {
+        for &(color, square) in camels {
+            if square >= self.spaces.len() {
+                return Err(TrackStateError::OutOfRange(square));
+            }
+            if camels.iter().filter(|&&(c, _)| c == color).count() > 1 {
+                return Err(TrackStateError::Duplicate(color));
+            }
+        }
+        for &color in CamelColor::RACERS.iter() {
+            if !camels.iter().any(|&(c, _)| c == color) {
+                return Err(TrackStateError::Missing(color));
+            }
+        }
+
+        let mut spaces: [Vec<CamelColor>; 16] = Default::default();
+        for &(color, square) in camels {
+            spaces[square].push(color);
+        }
+        for crazy in [CamelColor::Black, CamelColor::White] {
+            if !camels.iter().any(|&(c, _)| c == crazy) {
+                spaces[15].push(crazy);
+            }
+        }
+
+        self.spaces = spaces;
+        Ok(())
+    }
+
     fn advance(&mut self, color: CamelColor, number: usize) {
         let spaces = &mut self.spaces;
         let source = spaces
-            .into_iter()
+            .iter_mut()
             .position(|space| space.contains(&color))
             .expect("couldn't find camel");
 
         let stack = &mut spaces[source];
         let stack_position = stack
-            .into_iter()
+            .iter_mut()
             .position(|camel| camel == &color)
             .expect("couldn't find camel");
 
         let mut unit = stack.split_off(stack_position);
-        let destination = source + number;
+        // Black and White are the "crazy camels": they race backward, toward
+        // the start tile, carrying whatever is stacked on top of them just
+        // like a normal advance does. `saturating_sub` covers the edge case
+        // of a crazy camel already sitting on the start tile.
+        let last_space = spaces.len() - 1;
+        let destination = if color.moves_backward() {
+            source.saturating_sub(number)
+        } else {
+            (source + number).min(last_space)
+        };
         spaces[destination].append(&mut unit);
     }
 
-    fn print(&self) {
-        self.spaces.iter().enumerate().for_each(|(index, camels)| {
-            println!("{:x} : {:?}", index, camels);
-        })
+    /// The camel in last place, ignoring the crazy camels, which can sit
+    /// anywhere in a stack but never count toward the standings.
+    fn losing(&self) -> Option<CamelColor> {
+        self.spaces
+            .iter()
+            .find_map(|space| space.iter().copied().find(|camel| camel.is_racer()))
+    }
+
+    /// The camel in first place, ignoring the crazy camels.
+    fn winning(&self) -> Option<CamelColor> {
+        self.spaces
+            .iter()
+            .rev()
+            .find_map(|space| space.iter().rev().copied().find(|camel| camel.is_racer()))
+    }
+
+    /// Exhaustively enumerate every way the dice still sitting in the
+    /// pyramid (`remaining`) could come out for the rest of this leg, and
+    /// tally how often each racing camel ends up winning or losing the leg.
+    /// Only the dice that haven't been rolled yet are permuted, so this is
+    /// the correct conditional distribution mid-leg rather than a
+    /// from-scratch reroll of all five/seven dice: `k! * 3^k` outcomes for
+    /// `k` remaining dice. With no dice left the leg is already decided, so
+    /// the current standings are returned directly instead of enumerating
+    /// zero outcomes (which would otherwise divide by zero). With a full
+    /// seven-die pyramid, `k! * 3^k` is ~11 million outcomes -- too slow to
+    /// enumerate synchronously on every roll -- so `EXACT_LEG_OUTCOME_LIMIT`
+    /// or more remaining dice fall back to [`Track::sample_leg_outcomes`].
+    fn leg_outcomes(&self, remaining: &[CamelColor]) -> HashMap<CamelColor, (f64, f64)> {
+        let num_dice = remaining.len();
+        if num_dice == 0 {
+            return self.current_standings();
+        }
+        if num_dice >= EXACT_LEG_OUTCOME_LIMIT {
+            return self.sample_leg_outcomes(remaining, LEG_SAMPLE_COUNT);
+        }
+
+        let color_orders = remaining.iter().copied().permutations(num_dice);
+
+        let numbers: [usize; 3] = [1, 2, 3];
+        let number_rolls = (0..num_dice)
+            .map(|_| numbers.iter())
+            .multi_cartesian_product();
+
+        let outcomes = color_orders
+            .cartesian_product(number_rolls)
+            .collect::<Vec<_>>();
+        let total = outcomes.len() as f64;
+
+        let (win_tallies, loss_tallies) = self.tally_losers_parallel(&outcomes);
+
+        CamelColor::RACERS
+            .iter()
+            .map(|&color| {
+                let wins = *win_tallies.get(&color).unwrap_or(&0) as f64 / total;
+                let losses = *loss_tallies.get(&color).unwrap_or(&0) as f64 / total;
+                (color, (wins, losses))
+            })
+            .collect()
+    }
+
+    /// The standings as they sit right now: the current winner/loser each
+    /// get probability 1.0 and everyone else 0.0.
+    fn current_standings(&self) -> HashMap<CamelColor, (f64, f64)> {
+        let winner = self.winning();
+        let loser = self.losing();
+        CamelColor::RACERS
+            .iter()
+            .map(|&color| {
+                let win = if Some(color) == winner { 1.0 } else { 0.0 };
+                let lose = if Some(color) == loser { 1.0 } else { 0.0 };
+                (color, (win, lose))
+            })
+            .collect()
+    }
+
+    /// Tally, for each racing camel, how many of the given `outcomes` leave
+    /// it winning and losing the leg. Each outcome is independent, so the
+    /// work fans out across a rayon parallel iterator; a single simulation
+    /// per outcome folds into a per-thread `(wins, losses)` pair of tallies,
+    /// which are then reduced into the final maps -- computing both in one
+    /// pass instead of re-simulating every outcome twice.
+    fn tally_losers_parallel(
+        &self,
+        outcomes: &[(Vec<CamelColor>, Vec<&usize>)],
+    ) -> (HashMap<CamelColor, u32>, HashMap<CamelColor, u32>) {
+        let packed = PackedTrack::from(self);
+        outcomes
+            .par_iter()
+            .fold(
+                || (HashMap::new(), HashMap::new()),
+                |(mut wins, mut losses), (order, numbers)| {
+                    let mut simulation = packed;
+                    for (color, number) in order.iter().zip(numbers.iter()) {
+                        simulation.advance(*color, **number);
+                    }
+                    if let Some(winner) = simulation.winning() {
+                        *wins.entry(winner).or_insert(0) += 1;
+                    }
+                    if let Some(loser) = simulation.losing() {
+                        *losses.entry(loser).or_insert(0) += 1;
+                    }
+                    (wins, losses)
+                },
+            )
+            .reduce(|| (HashMap::new(), HashMap::new()), merge_tallies)
+    }
+
+    /// Monte Carlo fallback for [`Track::leg_outcomes`] when there are too
+    /// many remaining dice to enumerate exactly. Randomly rolls out the
+    /// remaining dice `n` times and tallies the fraction of samples that
+    /// leave each racer winning/losing.
+    fn sample_leg_outcomes(
+        &self,
+        remaining: &[CamelColor],
+        n: usize,
+    ) -> HashMap<CamelColor, (f64, f64)> {
+        let packed = PackedTrack::from(self);
+
+        let (win_tallies, loss_tallies) = (0..n)
+            .into_par_iter()
+            .fold(
+                || (HashMap::new(), HashMap::new(), remaining.to_vec()),
+                |(mut wins, mut losses, mut dice), _| {
+                    // `ThreadRng` isn't `Send`, so it's grabbed fresh here
+                    // rather than threaded through the fold accumulator.
+                    let mut rng = thread_rng();
+                    dice.shuffle(&mut rng);
+                    let mut simulation = packed;
+                    for &color in dice.iter() {
+                        let number = rng.gen_range(1..=3);
+                        simulation.advance(color, number);
+                    }
+                    if let Some(winner) = simulation.winning() {
+                        *wins.entry(winner).or_insert(0) += 1;
+                    }
+                    if let Some(loser) = simulation.losing() {
+                        *losses.entry(loser).or_insert(0) += 1;
+                    }
+                    (wins, losses, dice)
+                },
+            )
+            .map(|(wins, losses, _)| (wins, losses))
+            .reduce(|| (HashMap::new(), HashMap::new()), merge_tallies);
+
+        let total = n as f64;
+        CamelColor::RACERS
+            .iter()
+            .map(|&color| {
+                let wins = *win_tallies.get(&color).unwrap_or(&0) as f64 / total;
+                let losses = *loss_tallies.get(&color).unwrap_or(&0) as f64 / total;
+                (color, (wins, losses))
+            })
+            .collect()
+    }
+}
+
+type Tallies = (HashMap<CamelColor, u32>, HashMap<CamelColor, u32>);
+
+/// Merges two per-thread `(wins, losses)` tally pairs into one, adding
+/// counts for colors both sides saw. Shared by every rayon `reduce` step
+/// that combines win/loss tallies gathered from parallel simulations.
+fn merge_tallies((mut wins_a, mut losses_a): Tallies, (wins_b, losses_b): Tallies) -> Tallies {
+    for (color, count) in wins_b {
+        *wins_a.entry(color).or_insert(0) += count;
+    }
+    for (color, count) in losses_b {
+        *losses_a.entry(color).or_insert(0) += count;
+    }
+    (wins_a, losses_a)
+}
+
+/// At or above this many remaining dice, [`Track::leg_outcomes`] samples
+/// instead of enumerating exactly: `k! * 3^k` outcomes hits ~525 thousand at
+/// `k = 6` and ~11 million at `k = 7` (the full pyramid including the two
+/// crazy dice) -- both measured in the hundred-millisecond range or worse
+/// even parallelized, which is too slow to run synchronously on every roll.
+/// Since a 7-die pyramid drops to 6 remaining dice after the very first
+/// roll of the leg, `k = 6` is the common case, not an edge case.
+const EXACT_LEG_OUTCOME_LIMIT: usize = 6;
+
+/// Sample count used by [`Track::sample_leg_outcomes`].
+const LEG_SAMPLE_COUNT: usize = 20_000;
+
+/// Sample count used by [`Game::sample_full_games`]. Playing out a full game
+/// is cheaper than enumerating a single leg exactly, so this can run on the
+/// UI thread alongside the leg odds without a noticeable hitch.
+const OVERALL_SAMPLE_COUNT: usize = 5_000;
+
+/// The most camels that could ever be stacked on a single square: all five
+/// racers plus the two crazy camels.
+const MAX_STACK: usize = 7;
+
+/// A `Copy` packed encoding of a `Track`: each of the 16 squares is a
+/// fixed-size array of up to `MAX_STACK` camels (bottom-to-top) plus a
+/// length byte, so cloning a position is a plain memcpy instead of the 16
+/// heap allocations a `Vec`-based `Track` needs. Used by the simulation
+/// loops, which clone a position for every candidate outcome; `Track`
+/// stays around for display.
+#[derive(Clone, Copy)]
+struct PackedTrack {
+    squares: [[CamelColor; MAX_STACK]; 16],
+    heights: [u8; 16],
+}
+
+impl PackedTrack {
+    fn advance(&mut self, color: CamelColor, number: usize) {
+        let source = (0..16)
+            .find(|&square| {
+                self.squares[square][..self.heights[square] as usize].contains(&color)
+            })
+            .expect("couldn't find camel");
+
+        let height = self.heights[source] as usize;
+        let stack_position = self.squares[source][..height]
+            .iter()
+            .position(|camel| *camel == color)
+            .expect("couldn't find camel");
+
+        let lifted_len = height - stack_position;
+        let mut lifted = [CamelColor::Red; MAX_STACK];
+        lifted[..lifted_len].copy_from_slice(&self.squares[source][stack_position..height]);
+        self.heights[source] = stack_position as u8;
+
+        let last_space = self.heights.len() - 1;
+        let destination = if color.moves_backward() {
+            source.saturating_sub(number)
+        } else {
+            (source + number).min(last_space)
+        };
+
+        let dest_height = self.heights[destination] as usize;
+        self.squares[destination][dest_height..dest_height + lifted_len]
+            .copy_from_slice(&lifted[..lifted_len]);
+        self.heights[destination] = (dest_height + lifted_len) as u8;
     }
 
     fn losing(&self) -> Option<CamelColor> {
-        for space in self.spaces.iter() {
-            if let Some(camel) = space.first() {
-                return Some(*camel);
-            }
+        (0..16).find_map(|square| {
+            let height = self.heights[square] as usize;
+            self.squares[square][..height]
+                .iter()
+                .copied()
+                .find(|camel| camel.is_racer())
+        })
+    }
+
+    fn winning(&self) -> Option<CamelColor> {
+        (0..16).rev().find_map(|square| {
+            let height = self.heights[square] as usize;
+            self.squares[square][..height]
+                .iter()
+                .rev()
+                .copied()
+                .find(|camel| camel.is_racer())
+        })
+    }
+
+    fn finished(&self) -> bool {
+        let height = self.heights[15] as usize;
+        self.squares[15][..height]
+            .iter()
+            .any(|camel| camel.is_racer())
+    }
+}
+
+impl From<&Track> for PackedTrack {
+    fn from(track: &Track) -> Self {
+        let mut packed = PackedTrack {
+            squares: [[CamelColor::Red; MAX_STACK]; 16],
+            heights: [0; 16],
+        };
+        for (square, camels) in track.spaces.iter().enumerate() {
+            packed.squares[square][..camels.len()].copy_from_slice(camels);
+            packed.heights[square] = camels.len() as u8;
         }
-        None
+        packed
+    }
+}
+
+impl From<PackedTrack> for Track {
+    fn from(packed: PackedTrack) -> Self {
+        let mut track = Track::new();
+        for square in 0..16 {
+            let height = packed.heights[square] as usize;
+            track.spaces[square] = packed.squares[square][..height].to_vec();
+        }
+        track
     }
 }
 
@@ -74,8 +392,14 @@ impl Game {
     fn new() -> Self {
         let mut pyramid = Pyramid::new();
         let mut track = Track::new();
+        // The crazy camels always start stacked on the far end of the
+        // track, not at a random roll like the racing camels.
+        track.spaces[15].push(CamelColor::Black);
+        track.spaces[15].push(CamelColor::White);
         while let Some(roll) = pyramid.roll() {
-            track.spaces[roll.number - 1].push(roll.color);
+            if roll.color.is_racer() {
+                track.spaces[roll.number - 1].push(roll.color);
+            }
         }
         pyramid.reset();
         Game { track, pyramid }
@@ -83,16 +407,72 @@ impl Game {
 
     fn roll(&mut self) -> Option<Roll> {
         let roll = self.pyramid.roll()?;
-        println!("");
-        println!("{:?}", roll);
-        println!("");
-
         self.track.advance(roll.color, roll.number);
-
         Some(roll)
     }
+
+    /// The dice still sitting in the pyramid, i.e. the ones that haven't
+    /// been rolled yet this leg.
+    fn pending_dice(&self) -> &[CamelColor] {
+        &self.pyramid.dice
+    }
+
+    /// Empty and reshuffle the pyramid without moving any camels, starting
+    /// the next leg.
+    fn reset_leg(&mut self) {
+        self.pyramid.reset();
+    }
+
+    /// Monte Carlo estimate of the overall winner/loser probabilities.
+    /// Plays `n` complete games to the finish line from the current board
+    /// and pyramid, rolling out the rest of this leg and then emptying and
+    /// resetting the pyramid leg after leg until a camel finishes, exactly
+    /// like a real game would.
+    fn sample_full_games(&self, n: usize) -> HashMap<CamelColor, (f64, f64)> {
+        let (win_tallies, loss_tallies) = (0..n)
+            .into_par_iter()
+            .fold(
+                || (HashMap::new(), HashMap::new()),
+                |(mut wins, mut losses), _| {
+                    let mut track = PackedTrack::from(&self.track);
+                    let mut pyramid = self.pyramid.clone();
+                    // `pyramid.reset()` reshuffles every leg after this one,
+                    // but the pending dice for the *current* leg were popped
+                    // off in whatever order the real pyramid happened to be
+                    // in -- shuffle them too, or every playout resolves this
+                    // leg with the same hidden pull order and only the rolled
+                    // numbers vary.
+                    pyramid.dice.shuffle(&mut thread_rng());
+
+                    while !track.finished() {
+                        match pyramid.roll() {
+                            Some(roll) => track.advance(roll.color, roll.number),
+                            None => pyramid.reset(),
+                        }
+                    }
+
+                    let winner = track.winning().expect("a finished game has a winner");
+                    let loser = track.losing().expect("a finished game still has a loser");
+                    *wins.entry(winner).or_insert(0) += 1;
+                    *losses.entry(loser).or_insert(0) += 1;
+                    (wins, losses)
+                },
+            )
+            .reduce(|| (HashMap::new(), HashMap::new()), merge_tallies);
+
+        let total = n as f64;
+        CamelColor::RACERS
+            .iter()
+            .map(|&color| {
+                let wins = *win_tallies.get(&color).unwrap_or(&0) as f64 / total;
+                let losses = *loss_tallies.get(&color).unwrap_or(&0) as f64 / total;
+                (color, (wins, losses))
+            })
+            .collect()
+    }
 }
 
+#[derive(Clone)]
 struct Pyramid {
     dice: Vec<CamelColor>,
 }
@@ -112,6 +492,8 @@ impl Pyramid {
             CamelColor::Yellow,
             CamelColor::Blue,
             CamelColor::Purple,
+            CamelColor::Black,
+            CamelColor::White,
         ];
 
         dice.shuffle(&mut rng);
@@ -121,8 +503,10 @@ impl Pyramid {
 
     fn roll(&mut self) -> Option<Roll> {
         let color = self.dice.pop()?;
+        // Black and White dice show the same 1-3 faces as the racing dice;
+        // only `Track::advance` treats their roll as backward motion.
         let number: usize = thread_rng().gen_range(1..=3);
-        return Some(Roll { color, number });
+        Some(Roll { color, number })
     }
 
     fn reset(&mut self) {
@@ -133,6 +517,8 @@ impl Pyramid {
             CamelColor::Yellow,
             CamelColor::Blue,
             CamelColor::Purple,
+            CamelColor::Black,
+            CamelColor::White,
         ];
         self.dice.shuffle(&mut rng);
     }
@@ -149,92 +535,221 @@ enum CamelColor {
     White,
 }
 
-fn main() -> io::Result<()> {
-    let mut game = Game::new();
-    game.track.print();
-
-    let colors = vec![
+impl CamelColor {
+    /// The five colors racing for the finish line and counted in the
+    /// standings. Black and White are the "crazy camels": they can be
+    /// carried along in a stack but never win or lose a leg themselves.
+    const RACERS: [CamelColor; 5] = [
         CamelColor::Red,
         CamelColor::Green,
-        CamelColor::Blue,
         CamelColor::Yellow,
+        CamelColor::Blue,
         CamelColor::Purple,
     ];
-    let num_colors = colors.len();
-    let color_orders = colors.into_iter().permutations(num_colors);
-
-    let numbers: [usize; 3] = [1, 2, 3];
-    let number_rolls = (0..num_colors)
-        .map(|_| numbers.iter())
-        .multi_cartesian_product();
-
-    let outcomes = color_orders
-        .cartesian_product(number_rolls)
-        .collect::<Vec<_>>();
-
-    let mut loser_tallies = HashMap::from([
-        (CamelColor::Red, 0),
-        (CamelColor::Blue, 0),
-        (CamelColor::Green, 0),
-        (CamelColor::Yellow, 0),
-        (CamelColor::Purple, 0),
-    ]);
-
-    for outcome in outcomes.iter() {
-        let mut simulation = game.track.clone();
-        for roll in 0..num_colors {
-            let color = outcome.0[roll];
-            let number = outcome.1[roll];
-            simulation.advance(color, *number);
+
+    fn is_racer(&self) -> bool {
+        Self::RACERS.contains(self)
+    }
+
+    fn moves_backward(&self) -> bool {
+        matches!(self, CamelColor::Black | CamelColor::White)
+    }
+}
+
+impl std::str::FromStr for CamelColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "red" => Ok(CamelColor::Red),
+            "green" => Ok(CamelColor::Green),
+            "yellow" => Ok(CamelColor::Yellow),
+            "blue" => Ok(CamelColor::Blue),
+            "purple" => Ok(CamelColor::Purple),
+            "black" => Ok(CamelColor::Black),
+            "white" => Ok(CamelColor::White),
+            other => Err(format!("unknown camel color '{other}'")),
         }
-        if let Some(losing) = simulation.losing() {
-            let tally = loser_tallies.get_mut(&losing).unwrap();
-            *tally += 1;
+    }
+}
+
+/// Why a user-supplied board position was rejected by
+/// [`Track::set_state`].
+#[derive(Debug)]
+enum TrackStateError {
+    OutOfRange(usize),
+    Missing(CamelColor),
+    Duplicate(CamelColor),
+}
+
+impl std::fmt::Display for TrackStateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrackStateError::OutOfRange(square) => {
+                write!(f, "square {square} is outside the 16-square track")
+            }
+            TrackStateError::Missing(color) => write!(f, "{color:?} never appears on the track"),
+            TrackStateError::Duplicate(color) => write!(f, "{color:?} appears more than once"),
         }
     }
+}
 
-    println!("{:?}", loser_tallies);
+impl std::error::Error for TrackStateError {}
 
-    Ok(())
+/// Parse a `--state` value like `red:2,green:2,blue:5,purple:9,yellow:9`
+/// (square index per camel, listed bottom-to-top within a shared square)
+/// into the pairs `Track::set_state` expects.
+fn parse_state(input: &str) -> Result<Vec<(CamelColor, usize)>, String> {
+    input
+        .split(',')
+        .map(|entry| {
+            let (color, square) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("expected color:square, got '{entry}'"))?;
+            let color: CamelColor = color.trim().parse()?;
+            let square: usize = square
+                .trim()
+                .parse()
+                .map_err(|_| format!("'{square}' isn't a square index"))?;
+            Ok((color, square))
+        })
+        .collect()
+}
 
-    /*
-    let mut game = Game::new();
-    game.print_track();
+/// Which widget is capturing keystrokes right now.
+enum InputMode {
+    Normal,
+    EnteringState,
+}
 
-    game.roll();
+/// The live dashboard's state: the game itself, the leg and overall odds
+/// recomputed after every roll, and whatever the user is currently typing.
+struct App {
+    game: Game,
+    odds: HashMap<CamelColor, (f64, f64)>,
+    overall_odds: HashMap<CamelColor, (f64, f64)>,
+    input_mode: InputMode,
+    input: String,
+    message: Option<String>,
+}
 
-    game.print_track();
+impl App {
+    fn new(game: Game) -> Self {
+        let odds = game.track.leg_outcomes(game.pending_dice());
+        let overall_odds = game.sample_full_games(OVERALL_SAMPLE_COUNT);
+        App {
+            game,
+            odds,
+            overall_odds,
+            input_mode: InputMode::Normal,
+            input: String::new(),
+            message: None,
+        }
+    }
 
-    Ok(())
-    */
+    fn refresh_odds(&mut self) {
+        self.odds = self.game.track.leg_outcomes(self.game.pending_dice());
+        self.overall_odds = self.game.sample_full_games(OVERALL_SAMPLE_COUNT);
+    }
+
+    fn roll(&mut self) {
+        match self.game.roll() {
+            Some(_) => {
+                self.message = None;
+                self.refresh_odds();
+            }
+            None => {
+                self.message = Some("leg finished -- press n to start the next leg".into());
+            }
+        }
+    }
+
+    fn reset_leg(&mut self) {
+        self.game.reset_leg();
+        self.message = None;
+        self.refresh_odds();
+    }
+
+    fn begin_entering_state(&mut self) {
+        self.input.clear();
+        self.input_mode = InputMode::EnteringState;
+    }
+
+    fn cancel_entering_state(&mut self) {
+        self.input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn submit_state(&mut self) {
+        let outcome = parse_state(&self.input).and_then(|camels| {
+            self.game
+                .track
+                .set_state(&camels)
+                .map_err(|err| err.to_string())
+        });
+        match outcome {
+            Ok(()) => {
+                self.message = None;
+                self.refresh_odds();
+            }
+            Err(err) => self.message = Some(err),
+        }
+        self.input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut game = Game::new();
+
+    let state_arg = std::env::args().skip_while(|arg| arg != "--state").nth(1);
+    if let Some(state) = state_arg {
+        let camels =
+            parse_state(&state).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        game.track
+            .set_state(&camels)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    }
+
+    let mut app = App::new(game);
 
-    /*
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
     let mut should_quit = false;
-    let mut pos = 1.0;
     while !should_quit {
-        terminal.draw(|f| ui(f, pos))?;
-        should_quit = handle_events(&mut pos)?;
+        terminal.draw(|f| ui(f, &app))?;
+        should_quit = handle_events(&mut app)?;
     }
 
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
 
     Ok(())
-    */
 }
 
-fn handle_events(pos: &mut f64) -> io::Result<bool> {
+fn handle_events(app: &mut App) -> io::Result<bool> {
     if event::poll(std::time::Duration::from_millis(50))? {
         if let Event::Key(key) = event::read()? {
             if key.kind == event::KeyEventKind::Press {
-                match key.code {
-                    KeyCode::Char('q') => return Ok(true),
-                    KeyCode::Up => *pos += 1.0,
-                    _ => (),
+                match app.input_mode {
+                    InputMode::Normal => match key.code {
+                        KeyCode::Char('q') => return Ok(true),
+                        KeyCode::Char('r') => app.roll(),
+                        KeyCode::Char('n') => app.reset_leg(),
+                        KeyCode::Char('e') => app.begin_entering_state(),
+                        _ => (),
+                    },
+                    InputMode::EnteringState => match key.code {
+                        KeyCode::Enter => app.submit_state(),
+                        KeyCode::Esc => app.cancel_entering_state(),
+                        KeyCode::Backspace => {
+                            app.input.pop();
+                        }
+                        KeyCode::Char(c) => app.input.push(c),
+                        _ => (),
+                    },
                 }
             }
         }
@@ -243,33 +758,305 @@ fn handle_events(pos: &mut f64) -> io::Result<bool> {
     Ok(false)
 }
 
-fn ui(frame: &mut Frame, pos: f64) {
-    let layout = Layout::new(
+fn camel_color(color: CamelColor) -> Color {
+    match color {
+        CamelColor::Red => Color::Red,
+        CamelColor::Green => Color::Green,
+        CamelColor::Yellow => Color::Yellow,
+        CamelColor::Blue => Color::Blue,
+        CamelColor::Purple => Color::Magenta,
+        CamelColor::Black => Color::DarkGray,
+        CamelColor::White => Color::White,
+    }
+}
+
+fn track_canvas(track: &Track) -> impl Widget + '_ {
+    Canvas::default()
+        .block(Block::default().title("Race Track").borders(Borders::ALL))
+        .x_bounds([0.0, 16.0])
+        .y_bounds([0.0, 8.0])
+        .paint(move |ctx| {
+            for (square, camels) in track.spaces.iter().enumerate() {
+                for (height, &camel) in camels.iter().enumerate() {
+                    ctx.draw(&Rectangle {
+                        x: square as f64,
+                        y: height as f64,
+                        width: 1.0,
+                        height: 1.0,
+                        color: camel_color(camel),
+                    });
+                }
+            }
+        })
+}
+
+/// Renders a titled block of win/lose gauges, one pair of rows per racer.
+/// Shared by the leg-odds and overall-odds panels, which only differ in
+/// title and which odds map they're fed.
+fn render_odds_panel(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    odds: &HashMap<CamelColor, (f64, f64)>,
+) {
+    let panel = Block::default().title(title.to_string()).borders(Borders::ALL);
+    frame.render_widget(panel.clone(), area);
+    let rows = Layout::new(
         Direction::Vertical,
-        [Constraint::Percentage(50), Constraint::Percentage(50)],
+        CamelColor::RACERS.map(|_| Constraint::Length(2)),
+    )
+    .split(panel.inner(area));
+
+    for (row, &color) in rows.iter().zip(CamelColor::RACERS.iter()) {
+        let (win, lose) = odds.get(&color).copied().unwrap_or((0.0, 0.0));
+        // `Gauge::ratio` panics outside [0.0, 1.0]; clamp defensively in
+        // case a probability ever comes out slightly off due to rounding.
+        let (win, lose) = (win.clamp(0.0, 1.0), lose.clamp(0.0, 1.0));
+        let halves =
+            Layout::new(Direction::Vertical, [Constraint::Length(1); 2]).split(*row);
+        frame.render_widget(
+            Gauge::default()
+                .label(format!("{color:?} win {:.0}%", win * 100.0))
+                .gauge_style(Style::default().fg(camel_color(color)))
+                .ratio(win),
+            halves[0],
+        );
+        frame.render_widget(
+            Gauge::default()
+                .label(format!("{color:?} lose {:.0}%", lose * 100.0))
+                .gauge_style(Style::default().fg(camel_color(color)))
+                .ratio(lose),
+            halves[1],
+        );
+    }
+}
+
+fn ui(frame: &mut Frame, app: &App) {
+    let outer = Layout::new(
+        Direction::Vertical,
+        [Constraint::Min(0), Constraint::Length(3)],
     )
     .split(frame.size());
 
-    frame.render_widget(
-        Paragraph::new("Hello world")
-            .block(Block::default().title("Gretting").borders(Borders::ALL)),
-        layout[0],
-    );
-
-    frame.render_widget(
-        Canvas::default()
-            .block(Block::default().title("Race Track").borders(Borders::ALL))
-            .x_bounds([0.0, 16.0])
-            .y_bounds([0.0, 10.0])
-            .paint(|ctx| {
-                ctx.draw(&Rectangle {
-                    x: 1.0,
-                    y: pos,
-                    width: 1.0,
-                    height: 1.0,
-                    color: Color::Red,
-                });
-            }),
-        layout[1],
-    );
+    let main_area = Layout::new(
+        Direction::Horizontal,
+        [Constraint::Percentage(65), Constraint::Percentage(35)],
+    )
+    .split(outer[0]);
+
+    frame.render_widget(track_canvas(&app.game.track), main_area[0]);
+
+    let odds_column = Layout::new(
+        Direction::Vertical,
+        [Constraint::Percentage(50), Constraint::Percentage(50)],
+    )
+    .split(main_area[1]);
+
+    render_odds_panel(frame, odds_column[0], "Leg odds", &app.odds);
+    render_odds_panel(frame, odds_column[1], "Overall odds", &app.overall_odds);
+
+    let status = match app.input_mode {
+        InputMode::Normal => {
+            let message = app
+                .message
+                .as_deref()
+                .unwrap_or("r: roll   n: reset leg   e: enter position   q: quit");
+            Paragraph::new(message).block(Block::default().borders(Borders::ALL).title("Camel Cup"))
+        }
+        InputMode::EnteringState => Paragraph::new(format!(
+            "color:square,... e.g. red:2,green:2,blue:5,yellow:9,purple:9 -- Enter to apply, Esc to cancel: {}",
+            app.input
+        ))
+        .block(Block::default().borders(Borders::ALL).title("Enter position")),
+    };
+    frame.render_widget(status, outer[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_track_round_trips_through_track() {
+        let mut track = Track::new();
+        track
+            .set_state(&[
+                (CamelColor::Red, 2),
+                (CamelColor::Green, 2),
+                (CamelColor::Blue, 5),
+                (CamelColor::Yellow, 9),
+                (CamelColor::Purple, 9),
+                (CamelColor::Black, 9),
+                (CamelColor::White, 0),
+            ])
+            .expect("valid state");
+
+        let packed = PackedTrack::from(&track);
+        let round_tripped: Track = packed.into();
+
+        assert_eq!(track, round_tripped);
+    }
+
+    #[test]
+    fn crazy_camel_moves_backward_and_carries_the_stack_above_it() {
+        let mut track = Track::new();
+        track
+            .set_state(&[
+                (CamelColor::Red, 1),
+                (CamelColor::Green, 1),
+                (CamelColor::Blue, 1),
+                (CamelColor::Yellow, 1),
+                (CamelColor::Purple, 1),
+                (CamelColor::White, 5),
+                (CamelColor::Black, 5),
+            ])
+            .expect("valid state");
+
+        track.advance(CamelColor::White, 3);
+
+        let mut expected = Track::new();
+        expected
+            .set_state(&[
+                (CamelColor::Red, 1),
+                (CamelColor::Green, 1),
+                (CamelColor::Blue, 1),
+                (CamelColor::Yellow, 1),
+                (CamelColor::Purple, 1),
+                (CamelColor::White, 2),
+                (CamelColor::Black, 2),
+            ])
+            .expect("valid state");
+
+        assert_eq!(track, expected);
+    }
+
+    #[test]
+    fn crazy_camel_cannot_move_past_the_start_tile() {
+        let mut track = Track::new();
+        track
+            .set_state(&[
+                (CamelColor::Red, 1),
+                (CamelColor::Green, 1),
+                (CamelColor::Blue, 1),
+                (CamelColor::Yellow, 1),
+                (CamelColor::Purple, 1),
+                (CamelColor::White, 0),
+                (CamelColor::Black, 5),
+            ])
+            .expect("valid state");
+
+        track.advance(CamelColor::White, 3);
+
+        assert!(track.spaces[0].contains(&CamelColor::White));
+    }
+
+    #[test]
+    fn racer_cannot_move_past_the_finish_tile() {
+        let mut track = Track::new();
+        let last = track.spaces.len() - 1;
+        track
+            .set_state(&[
+                (CamelColor::Red, last),
+                (CamelColor::Green, 1),
+                (CamelColor::Blue, 1),
+                (CamelColor::Yellow, 1),
+                (CamelColor::Purple, 1),
+            ])
+            .expect("valid state");
+
+        track.advance(CamelColor::Red, 3);
+
+        assert!(track.spaces[last].contains(&CamelColor::Red));
+    }
+
+    #[test]
+    fn set_state_rejects_an_out_of_range_square() {
+        let mut track = Track::new();
+        let result = track.set_state(&[
+            (CamelColor::Red, 16),
+            (CamelColor::Green, 1),
+            (CamelColor::Blue, 1),
+            (CamelColor::Yellow, 1),
+            (CamelColor::Purple, 1),
+        ]);
+
+        assert!(matches!(result, Err(TrackStateError::OutOfRange(16))));
+    }
+
+    #[test]
+    fn set_state_rejects_a_duplicated_camel() {
+        let mut track = Track::new();
+        let result = track.set_state(&[
+            (CamelColor::Red, 1),
+            (CamelColor::Red, 2),
+            (CamelColor::Green, 1),
+            (CamelColor::Blue, 1),
+            (CamelColor::Yellow, 1),
+            (CamelColor::Purple, 1),
+        ]);
+
+        assert!(matches!(
+            result,
+            Err(TrackStateError::Duplicate(CamelColor::Red))
+        ));
+    }
+
+    #[test]
+    fn set_state_rejects_a_missing_racer() {
+        let mut track = Track::new();
+        let result = track.set_state(&[
+            (CamelColor::Red, 1),
+            (CamelColor::Green, 1),
+            (CamelColor::Blue, 1),
+            (CamelColor::Yellow, 1),
+        ]);
+
+        assert!(matches!(
+            result,
+            Err(TrackStateError::Missing(CamelColor::Purple))
+        ));
+    }
+
+    #[test]
+    fn sampled_leg_outcomes_agree_with_exact_enumeration_near_the_sampling_threshold() {
+        let mut track = Track::new();
+        track
+            .set_state(&[
+                (CamelColor::Red, 3),
+                (CamelColor::Green, 4),
+                (CamelColor::Blue, 4),
+                (CamelColor::Yellow, 6),
+                (CamelColor::Purple, 8),
+            ])
+            .expect("valid state");
+
+        // Five remaining dice is the largest case `leg_outcomes` still
+        // enumerates exactly before `EXACT_LEG_OUTCOME_LIMIT` hands off to
+        // `sample_leg_outcomes`; compare the two right at that boundary.
+        let remaining = [
+            CamelColor::Red,
+            CamelColor::Green,
+            CamelColor::Yellow,
+            CamelColor::Blue,
+            CamelColor::Purple,
+        ];
+        assert!(remaining.len() < EXACT_LEG_OUTCOME_LIMIT);
+
+        let exact = track.leg_outcomes(&remaining);
+        let sampled = track.sample_leg_outcomes(&remaining, 50_000);
+
+        let tolerance = 0.03;
+        for &color in CamelColor::RACERS.iter() {
+            let (exact_win, exact_lose) = exact[&color];
+            let (sampled_win, sampled_lose) = sampled[&color];
+            assert!(
+                (exact_win - sampled_win).abs() < tolerance,
+                "{color:?} win probability: exact {exact_win}, sampled {sampled_win}"
+            );
+            assert!(
+                (exact_lose - sampled_lose).abs() < tolerance,
+                "{color:?} lose probability: exact {exact_lose}, sampled {sampled_lose}"
+            );
+        }
+    }
 }